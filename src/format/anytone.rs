@@ -0,0 +1,102 @@
+// Writer for the CSV layout used by AnyTone's CPS ("D878UV" family) import.
+// Its channel file holds DJ-MD5's first twelve columns plus colour code and
+// slot, with different channel type and power level naming; its contact file
+// has a single name column and no address columns. Reading is not supported.
+
+use super::{quote, Format};
+use crate::model::{CallType, Channel, Contact, Mode, Power};
+use std::error::Error;
+use std::io::Write;
+
+fn call_type_str(c: CallType) -> &'static str {
+    match c {
+        CallType::Private => "Private Call",
+        CallType::Group => "Group Call",
+        CallType::AllCall => "All Call",
+    }
+}
+
+pub struct AnyToneFormat;
+
+impl Format for AnyToneFormat {
+    fn name(&self) -> &'static str {
+        "anytone"
+    }
+
+    fn write_contacts(&self, w: &mut dyn Write, contacts: &[Contact]) -> Result<(), Box<dyn Error>> {
+        let fields = vec!["No.", "Radio ID", "Name", "Call Type", "Call Alert"];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        for (i, cnt) in contacts.iter().enumerate() {
+            let ent = format!(
+                "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                i + 1,
+                cnt.dmr_id,
+                cnt.name.trim(),
+                call_type_str(cnt.call_type),
+                "None"
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn write_channels(&self, w: &mut dyn Write, channels: &[Channel]) -> Result<(), Box<dyn Error>> {
+        let fields = vec![
+            "No.",
+            "Channel Name",
+            "Receive Frequency",
+            "Transmit Frequency",
+            "Channel Type",
+            "Transmit Power",
+            "Band Width",
+            "CTCSS/DCS Decode",
+            "CTCSS/DCS Encode",
+            "Contact",
+            "Contact Call Type",
+            "Contact TG/DMR ID",
+            "Color Code",
+            "Slot",
+        ];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        for (i, ch) in channels.iter().enumerate() {
+            let modulation = match ch.mode {
+                Mode::Dmr => "DMR",
+                Mode::Fm => "Analog",
+            };
+            // AnyTone has a fourth, "Turbo" power level; we only ever emit
+            // the three the neutral model knows about.
+            let power = match ch.power {
+                Power::Low => "Low",
+                Power::Medium => "Mid",
+                Power::High => "High",
+            };
+            let ctcss_r = ch.ctcss_rx.map_or(String::from("Off"), |t| format!("{:.1}", t));
+            let ctcss_t = ch.ctcss_tx.map_or(String::from("Off"), |t| format!("{:.1}", t));
+            let tg = ch.talkgroup.map_or(String::new(), |t| t.to_string());
+            let ent = format!(
+                "\"{}\",\"{}\",\"{:.5}\",\"{:.5}\",\"{}\",\"{}\",\"{}K\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                i + 1,
+                ch.name,
+                ch.rx_freq,
+                ch.tx_freq,
+                modulation,
+                power,
+                ch.bandwidth,
+                ctcss_r,
+                ctcss_t,
+                tg,
+                call_type_str(ch.call_type),
+                tg,
+                ch.colour,
+                ch.slot
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        println!("Saved {} channels", channels.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+// "Contact Manager" CSV backend. CM is only ever used as an input in
+// practice (it's the export format of the Contact Manager PC application),
+// so only the reader side is implemented.
+
+use super::Format;
+use crate::model::{CallType, Channel, Contact, Mode, Power};
+use csv::StringRecord;
+use serde::Deserialize;
+use std::error::Error;
+use std::io::Read;
+
+// Struct that allows "Contact Manager" contacts CSV files to be deserialised with serde
+#[derive(Debug, Deserialize)]
+struct CmContact {
+    dmr_id: u32,       // "2429135"
+    call_name: String, // "LA5AUA Stefan "
+    call_type: String, // "Private Call"
+    _alert: String,    // "No"
+    _ignore1: String,  // ""
+    _ignore2: String,  // ""
+    _ignore3: String,  // ""
+    _ignore4: String,  // ""
+    _ignore5: String,  // ""
+}
+
+// Struct that allows "Contact Manager" channel CSV files to be deserialised with serde
+// Most fields are not named / identified yet, only the most important ones have been.
+#[derive(Debug, Deserialize)]
+struct CmChannel {
+    name: String,      // "433.400 FMN"
+    mode: String,      // "FM"
+    bwidth: f64,       // "12.5"
+    tx_freq: f64,      // "433.400000"
+    rx_freq: f64,      // "433.400000"
+    _ignore1: String,  // "-NULL-"
+    _ignore2: String,  // "NORMAL"
+    _ignore3: String,  // "Channel Free"
+    _ignore4: String,  // "Medium"
+    _ignore5: String,  // "Medium"
+    _ignore6: String,  // "90"
+    _ignore7: String,  // "0"
+    power: String,     // "HIGH"
+    _ignore8: String,  // "No"
+    _ignore9: String,  // "No"
+    _ignore10: String, // "No"
+    _ignore11: String, // "No"
+    _ignore12: String, // "Yes"
+    ctcss_r: String,   // "NONE"
+    ctcss_t: String,   // "NONE"
+    _ignore15: String, // "180"
+    _ignore16: String, // "Off
+    _ignore17: String, // "Off"
+    _ignore18: String, // "YES"
+    _ignore19: String, // "NO"
+    _ignore20: String, // "NO"
+    _ignore21: String, // "NO"
+    _ignore22: String, // "NO"
+    _ignore23: String, // "NO"
+    _ignore24: String, // "NO"
+    _ignore25: String, // "NO"
+    _ignore26: String, // "NO"
+    _ignore27: String, // "YES"
+    _ignore28: String, // "NO"
+    _ignore29: String, // "YES"
+    _ignore30: String, // "NONE"
+    group_id: String,  // "NONE"
+    _ignore32: String, // "NONE"
+    colour: i32,       // "1"
+    _ignore34: String, // "NONE"
+    _ignore35: String, // "16"
+    slot: i32,         // "2"
+}
+
+fn call_type(s: &str) -> CallType {
+    match s {
+        "Private Call" => CallType::Private,
+        "All Call" => CallType::AllCall,
+        _ => CallType::Group,
+    }
+}
+
+fn ctcss(s: &str) -> Option<f64> {
+    match s {
+        "None" | "NONE" | "000.0" => None,
+        s => s.parse::<f64>().ok(),
+    }
+}
+
+pub struct CmFormat;
+
+impl Format for CmFormat {
+    fn name(&self) -> &'static str {
+        "cm"
+    }
+
+    fn read_contacts(&self, r: &mut dyn Read) -> Result<Vec<Contact>, Box<dyn Error>> {
+        let mut rdr = csv::Reader::from_reader(r);
+        rdr.set_headers(StringRecord::from(vec![
+            "dmr_id",
+            "call_name",
+            "call_type",
+            "_alert",
+            "_ignore1",
+            "_ignore2",
+            "_ignore3",
+            "_ignore4",
+            "_ignore5",
+        ]));
+        let mut contacts = Vec::new();
+        for result in rdr.deserialize() {
+            let record: CmContact = result?;
+            contacts.push(Contact {
+                dmr_id: record.dmr_id,
+                name: record.call_name.trim().to_owned(),
+                call_type: call_type(&record.call_type),
+            });
+        }
+        Ok(contacts)
+    }
+
+    fn read_channels(&self, r: &mut dyn Read) -> Result<Vec<Channel>, Box<dyn Error>> {
+        let mut rdr = csv::Reader::from_reader(r);
+        let fields = vec![
+            "name",
+            "mode",
+            "bwidth",
+            "tx_freq",
+            "rx_freq",
+            "_ignore1",
+            "_ignore2",
+            "_ignore3",
+            "_ignore4",
+            "_ignore5",
+            "_ignore6",
+            "_ignore7",
+            "power",
+            "_ignore8",
+            "_ignore9",
+            "_ignore10",
+            "_ignore11",
+            "_ignore12",
+            "ctcss_r",
+            "ctcss_t",
+            "_ignore15",
+            "_ignore16",
+            "_ignore17",
+            "_ignore18",
+            "_ignore19",
+            "_ignore20",
+            "_ignore21",
+            "_ignore22",
+            "_ignore23",
+            "_ignore24",
+            "_ignore25",
+            "_ignore26",
+            "_ignore27",
+            "_ignore28",
+            "_ignore29",
+            "_ignore30",
+            "group_id",
+            "_ignore32",
+            "colour",
+            "_ignore34",
+            "_ignore35",
+            "slot",
+        ];
+        rdr.set_headers(StringRecord::from(fields));
+        let mut channels = Vec::new();
+        for result in rdr.deserialize() {
+            let chan: CmChannel = result?;
+            let talkgroup = chan
+                .group_id
+                .split(' ')
+                .next()
+                .and_then(|n| n.parse::<u32>().ok());
+            channels.push(Channel {
+                name: chan.name,
+                mode: match chan.mode.as_str() {
+                    "DMR" => Mode::Dmr,
+                    _ => Mode::Fm,
+                },
+                bandwidth: chan.bwidth,
+                tx_freq: chan.tx_freq,
+                rx_freq: chan.rx_freq,
+                power: match chan.power.as_str() {
+                    "LOW" => Power::Low,
+                    "MEDIUM" => Power::Medium,
+                    _ => Power::High,
+                },
+                ctcss_rx: ctcss(&chan.ctcss_r),
+                ctcss_tx: ctcss(&chan.ctcss_t),
+                colour: chan.colour,
+                slot: chan.slot,
+                talkgroup,
+                call_type: CallType::Group,
+            });
+        }
+        Ok(channels)
+    }
+}
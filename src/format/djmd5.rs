@@ -0,0 +1,283 @@
+// Reader and writer for the CSV layout of the DJ-MD5's "CPS" (Customer
+// Programming Software) import and export functions.
+
+use super::{quote, Format};
+use crate::model::{CallType, Channel, Contact, Mode, Power};
+use crate::zone::ZoneStrategy;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{Read, Write};
+
+fn call_type_str(c: CallType) -> &'static str {
+    match c {
+        CallType::Private => "Private Call",
+        CallType::Group => "Group Call",
+        CallType::AllCall => "All Call",
+    }
+}
+
+fn parse_call_type(s: &str) -> CallType {
+    match s {
+        "Private Call" => CallType::Private,
+        "All Call" => CallType::AllCall,
+        _ => CallType::Group,
+    }
+}
+
+fn parse_power(s: &str) -> Power {
+    match s {
+        "Low" => Power::Low,
+        "Mid" => Power::Medium,
+        _ => Power::High,
+    }
+}
+
+fn parse_ctcss(s: &str) -> Option<f64> {
+    match s {
+        "Off" => None,
+        s => s.parse::<f64>().ok(),
+    }
+}
+
+pub struct DjMd5Format;
+
+impl Format for DjMd5Format {
+    fn name(&self) -> &'static str {
+        "djmd5"
+    }
+
+    // Read a DJ-MD5 CPS contacts export back into the neutral model, the
+    // reverse of `write_contacts` below.
+    fn read_contacts(&self, r: &mut dyn Read) -> Result<Vec<Contact>, Box<dyn Error>> {
+        let mut rdr = csv::Reader::from_reader(r);
+        let mut contacts = Vec::new();
+        for result in rdr.records() {
+            let rec = result?;
+            let dmr_id: u32 = rec.get(1).unwrap_or("").parse().unwrap_or(0);
+            let callsign = rec.get(2).unwrap_or("").trim();
+            let rest = rec.get(3).unwrap_or("").trim();
+            let name = match (callsign.is_empty(), rest.is_empty()) {
+                (true, true) => String::new(),
+                (false, true) => callsign.to_owned(),
+                (true, false) => rest.to_owned(),
+                (false, false) if callsign == rest => callsign.to_owned(),
+                (false, false) => format!("{} {}", callsign, rest),
+            };
+            contacts.push(Contact {
+                dmr_id,
+                name,
+                call_type: parse_call_type(rec.get(8).unwrap_or("")),
+            });
+        }
+        Ok(contacts)
+    }
+
+    // Read a DJ-MD5 CPS channels export (the 37-column layout written by
+    // `write_channels` below) back into the neutral model. This is what makes
+    // it possible to edit an existing radio codeplug or migrate it to
+    // another format with `--from djmd5 --to <other>`.
+    fn read_channels(&self, r: &mut dyn Read) -> Result<Vec<Channel>, Box<dyn Error>> {
+        let mut rdr = csv::Reader::from_reader(r);
+        let mut channels = Vec::new();
+        for result in rdr.records() {
+            let rec = result?;
+            let mode = match rec.get(4).unwrap_or("") {
+                "D-Digital" => Mode::Dmr,
+                _ => Mode::Fm,
+            };
+            let bandwidth = rec
+                .get(6)
+                .unwrap_or("")
+                .trim_end_matches('K')
+                .parse()
+                .unwrap_or(12.5);
+            channels.push(Channel {
+                name: rec.get(1).unwrap_or("").to_owned(),
+                mode,
+                bandwidth,
+                tx_freq: rec.get(3).unwrap_or("0").parse().unwrap_or(0.0),
+                rx_freq: rec.get(2).unwrap_or("0").parse().unwrap_or(0.0),
+                power: parse_power(rec.get(5).unwrap_or("")),
+                ctcss_rx: parse_ctcss(rec.get(7).unwrap_or("")),
+                ctcss_tx: parse_ctcss(rec.get(8).unwrap_or("")),
+                colour: rec.get(20).unwrap_or("0").parse().unwrap_or(0),
+                slot: rec.get(21).unwrap_or("1").parse().unwrap_or(1),
+                talkgroup: rec.get(11).and_then(|s| s.parse::<u32>().ok()),
+                call_type: parse_call_type(rec.get(10).unwrap_or("")),
+            });
+        }
+        Ok(channels)
+    }
+
+    fn write_contacts(&self, w: &mut dyn Write, contacts: &[Contact]) -> Result<(), Box<dyn Error>> {
+        let fields = vec![
+            "No.",
+            "Radio ID",
+            "Callsign",
+            "Name",
+            "City",
+            "State",
+            "Country",
+            "Remarks",
+            "Call Type",
+            "Call Alert",
+        ];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        for (i, cnt) in contacts.iter().enumerate() {
+            let name = cnt.name.trim();
+            let (callsign, rest) = match name.find(' ') {
+                None => (name, name),
+                Some(p) => (&name[..p], &name[(p + 1)..]),
+            };
+            let ent = format!(
+                "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                i + 1,
+                cnt.dmr_id,
+                callsign,
+                rest,
+                "",
+                "",
+                "",
+                "",
+                call_type_str(cnt.call_type),
+                "None"
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn write_channels(&self, w: &mut dyn Write, channels: &[Channel]) -> Result<(), Box<dyn Error>> {
+        let fields = vec![
+            "No.",
+            "Channel Name",
+            "Receive Frequency",
+            "Transmit Frequency",
+            "Channel Type",
+            "Transmit Power",
+            "Band Width",
+            "CTCSS/DCS Decode",
+            "CTCSS/DCS Encode",
+            "Contact",
+            "Contact Call Type",
+            "Contact TG/DMR ID",
+            "Radio ID",
+            "Busy Lock/TX Permit",
+            "Squelch Mode",
+            "Optional Signal",
+            "DTMF ID",
+            "2Tone ID",
+            "5Tone ID",
+            "PTT ID",
+            "Color Code",
+            "Slot",
+            "Scan List",
+            "Receive Group List",
+            "TX Prohibit",
+            "Reverse",
+            "Simplex TDMA",
+            "TDMA Adaptive",
+            "Encryption Type",
+            "Digital Encryption",
+            "Call Confirmation",
+            "Talk Around",
+            "Work Alone",
+            "Custom CTCSS",
+            "2TONE Decode",
+            "Ranging",
+            "Through Mode",
+        ];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        for (i, ch) in channels.iter().enumerate() {
+            let modulation = match ch.mode {
+                Mode::Dmr => "D-Digital",
+                Mode::Fm => "A-Analog",
+            };
+            let power = match ch.power {
+                Power::Low => "Low",
+                Power::Medium => "Mid",
+                Power::High => "Turbo",
+            };
+            let ctcss_r = ch.ctcss_rx.map_or(String::from("Off"), |t| format!("{:.1}", t));
+            let ctcss_t = ch.ctcss_tx.map_or(String::from("Off"), |t| format!("{:.1}", t));
+            let tg = ch.talkgroup.map_or(String::new(), |t| t.to_string());
+            let ent = format!("\"{}\",\"{}\",\"{:.5}\",\"{:.5}\",\"{}\",\"{}\",\"{}K\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                i+1, ch.name, ch.rx_freq, ch.tx_freq, modulation,
+                power, ch.bandwidth, ctcss_r, ctcss_t, tg, call_type_str(ch.call_type),
+                tg, "","Always","Carrier","Off","1",
+                "1","1","Off",ch.colour, ch.slot,"None","None","Off",
+                "Off","Off","Off","Normal Encryption","Off","Off",
+                "Off","Off","251.1","0","Off","Off");
+            w.write_all(ent.as_bytes())?;
+        }
+        println!("Saved {} channels", channels.len());
+        Ok(())
+    }
+
+    fn write_groups(&self, w: &mut dyn Write, channels: &[Channel]) -> Result<(), Box<dyn Error>> {
+        let fields = vec!["No.", "Radio ID", "Name", "Call Type", "Call Alert"];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        let mut groups: HashMap<u32, ()> = HashMap::new();
+        let mut count = 0;
+        for ch in channels {
+            let tg = match ch.talkgroup {
+                Some(tg) => tg,
+                None => continue,
+            };
+            if groups.insert(tg, ()).is_some() {
+                continue;
+            }
+            count += 1;
+            let ent = format!(
+                "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                count, tg, tg, "Group Call", "None"
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        println!("Saved {} groups", count);
+        Ok(())
+    }
+
+    fn write_zones(
+        &self,
+        w: &mut dyn Write,
+        channels: &[Channel],
+        strategy: &dyn ZoneStrategy,
+    ) -> Result<(), Box<dyn Error>> {
+        let fields = vec![
+            "No.",
+            "Zone Name",
+            "Zone Channel Member",
+            "A Channel",
+            "B Channel",
+        ];
+        let f2 = quote(&fields).join(",");
+        w.write_all(f2.as_bytes())?;
+        w.write_all(b"\r\n")?;
+        let mut zones = strategy.partition(channels)?;
+        zones.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut i = 0;
+        for zone in zones.iter_mut() {
+            if zone.channels.is_empty() {
+                continue;
+            }
+            zone.channels.sort();
+            let chan_a = zone.channels[0].clone();
+            let chan_b = zone.channels[zone.channels.len() - 1].clone();
+            let clist = zone.channels.join("|");
+            i += 1;
+            let ent = format!(
+                "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\r\n",
+                i, zone.name, clist, chan_a, chan_b
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+// Pluggable codeplug format backends, modeled on the `ilc` converter's
+// `format/mod.rs` dispatch between its energymech/irssi/weechat/msgpack
+// backends: each concrete format here knows how to read and/or write the
+// neutral model in `crate::model`, so the tool is a general codeplug
+// transcoder rather than a single fixed CM -> DJ-MD5 pipeline.
+
+pub mod anytone;
+pub mod cm;
+pub mod djmd5;
+pub mod opengd77;
+
+use crate::model::{Channel, Contact};
+use crate::zone::ZoneStrategy;
+use std::error::Error;
+use std::io::{Read, Write};
+
+pub trait Format {
+    fn name(&self) -> &'static str;
+
+    fn read_contacts(&self, _r: &mut dyn Read) -> Result<Vec<Contact>, Box<dyn Error>> {
+        Err(format!("{}: reading contacts is not supported", self.name()).into())
+    }
+
+    fn read_channels(&self, _r: &mut dyn Read) -> Result<Vec<Channel>, Box<dyn Error>> {
+        Err(format!("{}: reading channels is not supported", self.name()).into())
+    }
+
+    fn write_contacts(
+        &self,
+        _w: &mut dyn Write,
+        _contacts: &[Contact],
+    ) -> Result<(), Box<dyn Error>> {
+        Err(format!("{}: writing contacts is not supported", self.name()).into())
+    }
+
+    fn write_channels(
+        &self,
+        _w: &mut dyn Write,
+        _channels: &[Channel],
+    ) -> Result<(), Box<dyn Error>> {
+        Err(format!("{}: writing channels is not supported", self.name()).into())
+    }
+
+    // DJ-MD5 style radio ID group list, derived from the talkgroups referenced
+    // by `channels`. Formats that have no such concept just keep the default.
+    fn write_groups(&self, _w: &mut dyn Write, _channels: &[Channel]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    // Zone list grouping the channels, partitioned by `strategy`. Formats
+    // that have no such concept just keep the default.
+    fn write_zones(
+        &self,
+        _w: &mut dyn Write,
+        _channels: &[Channel],
+        _strategy: &dyn ZoneStrategy,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+// Double quote a vector of str, shared by the CSV-writing backends.
+pub(crate) fn quote(ins: &[&str]) -> Vec<String> {
+    ins.iter().map(|s| format!("\"{}\"", s)).collect()
+}
+
+// Resolve a `--from`/`--to` flag value to its format backend.
+pub fn by_name(name: &str) -> Result<Box<dyn Format>, Box<dyn Error>> {
+    match name {
+        "cm" => Ok(Box::new(cm::CmFormat)),
+        "djmd5" => Ok(Box::new(djmd5::DjMd5Format)),
+        "anytone" => Ok(Box::new(anytone::AnyToneFormat)),
+        "opengd77" => Ok(Box::new(opengd77::OpenGd77Format)),
+        other => Err(format!(
+            "unknown format '{}' (expected cm, djmd5, anytone, opengd77)",
+            other
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,80 @@
+// Writer for OpenGD77's firmware CSV layout, which is considerably
+// narrower than DJ-MD5/AnyTone's CPS exports and has no separate group/zone
+// files of its own (those are folded into the channel CSV's "Group List"
+// column by the OpenGD77 CPS).
+
+use super::Format;
+use crate::model::{CallType, Channel, Contact, Mode, Power};
+use std::error::Error;
+use std::io::Write;
+
+pub struct OpenGd77Format;
+
+// OpenGD77's CPS only distinguishes "Private" and "Group" contacts, so
+// `AllCall` degrades to "Group" (DJ-MD5/AnyTone keep it as "All Call").
+fn id_type_str(c: CallType) -> &'static str {
+    match c {
+        CallType::Private => "Private",
+        CallType::Group | CallType::AllCall => "Group",
+    }
+}
+
+impl Format for OpenGd77Format {
+    fn name(&self) -> &'static str {
+        "opengd77"
+    }
+
+    fn write_contacts(&self, w: &mut dyn Write, contacts: &[Contact]) -> Result<(), Box<dyn Error>> {
+        w.write_all(b"Contact Name,ID,ID Type,TS Override\r\n")?;
+        for cnt in contacts {
+            let ent = format!(
+                "{},{},{},None\r\n",
+                cnt.name.trim(),
+                cnt.dmr_id,
+                id_type_str(cnt.call_type)
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn write_channels(&self, w: &mut dyn Write, channels: &[Channel]) -> Result<(), Box<dyn Error>> {
+        w.write_all(
+            b"Channel Number,Channel Name,Channel Type,Rx Frequency,Tx Frequency,Colour Code,Timeslot,Contact,TX Power,Bandwidth,Rx Tone,Tx Tone\r\n",
+        )?;
+        for (i, ch) in channels.iter().enumerate() {
+            let channel_type = match ch.mode {
+                Mode::Dmr => "Digital",
+                Mode::Fm => "Analogue",
+            };
+            // OpenGD77 only distinguishes Master (full) and two reduced
+            // power levels; there is no room for a fourth "Turbo" tier.
+            let power = match ch.power {
+                Power::Low => "P1",
+                Power::Medium => "P5",
+                Power::High => "Master",
+            };
+            let rx_tone = ch.ctcss_rx.map_or(String::from("None"), |t| format!("{:.1}", t));
+            let tx_tone = ch.ctcss_tx.map_or(String::from("None"), |t| format!("{:.1}", t));
+            let tg = ch.talkgroup.map_or(String::new(), |t| t.to_string());
+            let ent = format!(
+                "{},{},{},{:.5},{:.5},{},{},{},{},{}K,{},{}\r\n",
+                i + 1,
+                ch.name,
+                channel_type,
+                ch.rx_freq,
+                ch.tx_freq,
+                ch.colour,
+                ch.slot,
+                tg,
+                power,
+                ch.bandwidth,
+                rx_tone,
+                tx_tone
+            );
+            w.write_all(ent.as_bytes())?;
+        }
+        println!("Saved {} channels", channels.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,457 @@
+// Codeplug validation/lint pass, modeled on rslint's rule+severity+autofix
+// design: a set of independent `Rule`s each scan the parsed channels/contacts
+// and report `Diagnostic`s, some of which carry a suggested fix that
+// `apply_fixes` can apply in place.
+
+use crate::model::{Channel, Contact, Mode};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub row: usize,
+    pub field: &'static str,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+pub trait Rule {
+    fn check(&self, channels: &[Channel], contacts: &[Contact]) -> Vec<Diagnostic>;
+}
+
+// Standard EIA CTCSS sub-audible tone table, in Hz.
+pub const CTCSS_TONES: &[f64] = &[
+    67.0, 69.3, 71.9, 74.4, 77.0, 79.7, 82.5, 85.4, 88.5, 91.5, 94.8, 97.4, 100.0, 103.5, 107.2,
+    110.9, 114.8, 118.8, 123.0, 127.3, 131.8, 136.5, 141.3, 146.2, 151.4, 156.7, 159.8, 162.2,
+    165.5, 167.9, 171.3, 173.8, 177.3, 179.9, 183.5, 186.2, 189.9, 192.8, 196.6, 199.5, 203.5,
+    206.5, 210.7, 218.1, 225.7, 229.1, 233.6, 241.8, 250.3, 254.1,
+];
+
+fn nearest_tone(freq: f64) -> f64 {
+    CTCSS_TONES
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - freq).abs().partial_cmp(&(b - freq).abs()).unwrap())
+        .unwrap()
+}
+
+fn is_known_tone(freq: f64) -> bool {
+    CTCSS_TONES.iter().any(|t| (t - freq).abs() < 0.05)
+}
+
+const MAX_CHANNEL_NAME_LEN: usize = 16;
+const BAND_2M: (f64, f64) = (144.0, 148.0);
+const BAND_70CM: (f64, f64) = (420.0, 450.0);
+
+fn in_amateur_band(freq: f64) -> bool {
+    (freq >= BAND_2M.0 && freq <= BAND_2M.1) || (freq >= BAND_70CM.0 && freq <= BAND_70CM.1)
+}
+
+pub struct DuplicateDmrIds;
+
+impl Rule for DuplicateDmrIds {
+    fn check(&self, _channels: &[Channel], contacts: &[Contact]) -> Vec<Diagnostic> {
+        let mut seen: HashMap<u32, usize> = HashMap::new();
+        let mut out = Vec::new();
+        for (row, c) in contacts.iter().enumerate() {
+            let first = *seen.entry(c.dmr_id).or_insert(row);
+            if first != row {
+                out.push(Diagnostic {
+                    severity: Severity::Error,
+                    row,
+                    field: "dmr_id",
+                    message: format!(
+                        "duplicate DMR ID {} (first seen at contact row {})",
+                        c.dmr_id,
+                        first + 1
+                    ),
+                    fix: None,
+                });
+            }
+        }
+        out
+    }
+}
+
+pub struct DuplicateChannelNames;
+
+impl Rule for DuplicateChannelNames {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        let mut out = Vec::new();
+        for (row, ch) in channels.iter().enumerate() {
+            let first = *seen.entry(ch.name.as_str()).or_insert(row);
+            if first != row {
+                out.push(Diagnostic {
+                    severity: Severity::Error,
+                    row,
+                    field: "name",
+                    message: format!(
+                        "duplicate channel name '{}' (first seen at channel row {}); the DJ-MD5 rejects duplicate names",
+                        ch.name,
+                        first + 1
+                    ),
+                    fix: None,
+                });
+            }
+        }
+        out
+    }
+}
+
+pub struct ChannelNameTooLong;
+
+impl Rule for ChannelNameTooLong {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| ch.name.chars().count() > MAX_CHANNEL_NAME_LEN)
+            .map(|(row, ch)| Diagnostic {
+                severity: Severity::Error,
+                row,
+                field: "name",
+                message: format!(
+                    "channel name '{}' is {} characters, over the radio's {}-character limit",
+                    ch.name,
+                    ch.name.chars().count(),
+                    MAX_CHANNEL_NAME_LEN
+                ),
+                fix: Some(ch.name.chars().take(MAX_CHANNEL_NAME_LEN).collect()),
+            })
+            .collect()
+    }
+}
+
+pub struct FrequencyOutOfBand;
+
+impl Rule for FrequencyOutOfBand {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for (row, ch) in channels.iter().enumerate() {
+            for (field, freq) in [("tx_freq", ch.tx_freq), ("rx_freq", ch.rx_freq)] {
+                if !in_amateur_band(freq) {
+                    out.push(Diagnostic {
+                        severity: Severity::Warning,
+                        row,
+                        field,
+                        message: format!(
+                            "{} {:.5} MHz on channel '{}' is outside the amateur 2m/70cm bands",
+                            field, freq, ch.name
+                        ),
+                        fix: None,
+                    });
+                }
+            }
+        }
+        out
+    }
+}
+
+pub struct InvalidSlot;
+
+impl Rule for InvalidSlot {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| ch.slot != 1 && ch.slot != 2)
+            .map(|(row, ch)| Diagnostic {
+                severity: Severity::Error,
+                row,
+                field: "slot",
+                message: format!(
+                    "channel '{}' has slot {}, must be 1 or 2",
+                    ch.name, ch.slot
+                ),
+                fix: None,
+            })
+            .collect()
+    }
+}
+
+pub struct InvalidColourCode;
+
+impl Rule for InvalidColourCode {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        channels
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| !(0..=15).contains(&ch.colour))
+            .map(|(row, ch)| Diagnostic {
+                severity: Severity::Error,
+                row,
+                field: "colour",
+                message: format!(
+                    "channel '{}' has colour code {}, must be in 0..=15",
+                    ch.name, ch.colour
+                ),
+                fix: None,
+            })
+            .collect()
+    }
+}
+
+pub struct InvalidCtcssTone;
+
+impl Rule for InvalidCtcssTone {
+    fn check(&self, channels: &[Channel], _contacts: &[Contact]) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for (row, ch) in channels.iter().enumerate() {
+            for (field, tone) in [("ctcss_rx", ch.ctcss_rx), ("ctcss_tx", ch.ctcss_tx)] {
+                if let Some(freq) = tone {
+                    if !freq.is_finite() {
+                        out.push(Diagnostic {
+                            severity: Severity::Warning,
+                            row,
+                            field,
+                            message: format!(
+                                "channel '{}' {} tone {} Hz is not a valid number",
+                                ch.name, field, freq
+                            ),
+                            fix: None,
+                        });
+                    } else if !is_known_tone(freq) {
+                        let snapped = nearest_tone(freq);
+                        out.push(Diagnostic {
+                            severity: Severity::Warning,
+                            row,
+                            field,
+                            message: format!(
+                                "channel '{}' {} tone {:.1} Hz is not a standard CTCSS tone",
+                                ch.name, field, freq
+                            ),
+                            fix: Some(format!("{:.1}", snapped)),
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+pub struct UnknownTalkgroup;
+
+impl Rule for UnknownTalkgroup {
+    fn check(&self, channels: &[Channel], contacts: &[Contact]) -> Vec<Diagnostic> {
+        let known: std::collections::HashSet<u32> = contacts.iter().map(|c| c.dmr_id).collect();
+        channels
+            .iter()
+            .enumerate()
+            .filter_map(|(row, ch)| {
+                let tg = ch.talkgroup?;
+                if ch.mode == Mode::Dmr && !known.contains(&tg) {
+                    Some(Diagnostic {
+                        severity: Severity::Error,
+                        row,
+                        field: "talkgroup",
+                        message: format!(
+                            "channel '{}' references talkgroup {} which is absent from the contacts list",
+                            ch.name, tg
+                        ),
+                        fix: None,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+fn rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(DuplicateDmrIds),
+        Box::new(DuplicateChannelNames),
+        Box::new(ChannelNameTooLong),
+        Box::new(FrequencyOutOfBand),
+        Box::new(InvalidSlot),
+        Box::new(InvalidColourCode),
+        Box::new(InvalidCtcssTone),
+        Box::new(UnknownTalkgroup),
+    ]
+}
+
+// Run every rule over the parsed codeplug and collect the diagnostics.
+pub fn run(channels: &[Channel], contacts: &[Contact]) -> Vec<Diagnostic> {
+    rules()
+        .iter()
+        .flat_map(|rule| rule.check(channels, contacts))
+        .collect()
+}
+
+// Print diagnostics grouped by severity, errors first.
+pub fn report(diagnostics: &[Diagnostic]) {
+    for severity in [Severity::Error, Severity::Warning] {
+        for d in diagnostics.iter().filter(|d| d.severity == severity) {
+            let label = match d.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!(
+                "{}: row {} field '{}': {}",
+                label,
+                d.row + 1,
+                d.field,
+                d.message
+            );
+        }
+    }
+}
+
+// Apply every diagnostic's suggested fix, if any, to the offending channel.
+pub fn apply_fixes(channels: &mut [Channel], diagnostics: &[Diagnostic]) {
+    for d in diagnostics {
+        let fix = match &d.fix {
+            Some(f) => f,
+            None => continue,
+        };
+        let ch = match channels.get_mut(d.row) {
+            Some(ch) => ch,
+            None => continue,
+        };
+        match d.field {
+            "name" => ch.name = fix.clone(),
+            "ctcss_rx" => ch.ctcss_rx = fix.parse().ok(),
+            "ctcss_tx" => ch.ctcss_tx = fix.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CallType;
+
+    fn channel(name: &str) -> Channel {
+        Channel {
+            name: name.to_owned(),
+            ..Channel::default()
+        }
+    }
+
+    #[test]
+    fn duplicate_dmr_ids_flags_repeats() {
+        let contacts = vec![
+            Contact { dmr_id: 1, name: "A".into(), call_type: CallType::Group },
+            Contact { dmr_id: 2, name: "B".into(), call_type: CallType::Group },
+            Contact { dmr_id: 1, name: "C".into(), call_type: CallType::Group },
+        ];
+        let out = DuplicateDmrIds.check(&[], &contacts);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].row, 2);
+        assert_eq!(out[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn duplicate_channel_names_flags_repeats() {
+        let channels = vec![channel("CH1"), channel("CH2"), channel("CH1")];
+        let out = DuplicateChannelNames.check(&channels, &[]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].row, 2);
+    }
+
+    #[test]
+    fn channel_name_too_long_suggests_truncated_fix() {
+        let channels = vec![channel("WAY_TOO_LONG_NAME")];
+        let out = ChannelNameTooLong.check(&channels, &[]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].fix.as_deref(), Some("WAY_TOO_LONG_NAM"));
+    }
+
+    #[test]
+    fn truncation_fix_can_create_a_new_duplicate() {
+        // Two distinct over-long names that share the same 16-character
+        // prefix truncate to the same fixed name; apply_fixes doesn't
+        // re-check for the duplicate it just introduced, so a second lint
+        // pass is needed to catch it (which is what `main` does).
+        let mut channels = vec![
+            channel("IDENTICAL_PREFIX_A"),
+            channel("IDENTICAL_PREFIX_B"),
+        ];
+        let diagnostics = ChannelNameTooLong.check(&channels, &[]);
+        apply_fixes(&mut channels, &diagnostics);
+        assert_eq!(channels[0].name, channels[1].name);
+        let after = DuplicateChannelNames.check(&channels, &[]);
+        assert_eq!(after.len(), 1);
+    }
+
+    #[test]
+    fn frequency_out_of_band_warns_outside_2m_70cm() {
+        let mut ch = channel("OUT");
+        ch.tx_freq = 27.205;
+        ch.rx_freq = 27.205;
+        let out = FrequencyOutOfBand.check(&[ch], &[]);
+        assert_eq!(out.len(), 2);
+        assert!(out.iter().all(|d| d.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn invalid_slot_rejects_anything_but_1_or_2() {
+        let mut ch = channel("BAD_SLOT");
+        ch.slot = 5;
+        let out = InvalidSlot.check(&[ch], &[]);
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn invalid_colour_code_rejects_out_of_range() {
+        let mut ch = channel("BAD_COLOUR");
+        ch.colour = 16;
+        let out = InvalidColourCode.check(&[ch], &[]);
+        assert_eq!(out.len(), 1);
+    }
+
+    #[test]
+    fn invalid_ctcss_tone_suggests_nearest_standard_tone() {
+        let mut ch = channel("OFF_TONE");
+        ch.ctcss_rx = Some(100.3);
+        let out = InvalidCtcssTone.check(&[ch], &[]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].fix.as_deref(), Some("100.0"));
+    }
+
+    #[test]
+    fn invalid_ctcss_tone_does_not_panic_on_non_finite_values() {
+        let mut ch = channel("NAN_TONE");
+        ch.ctcss_rx = Some(f64::NAN);
+        let out = InvalidCtcssTone.check(&[ch], &[]);
+        assert_eq!(out.len(), 1);
+        assert!(out[0].fix.is_none());
+    }
+
+    #[test]
+    fn unknown_talkgroup_only_applies_to_dmr_channels() {
+        let mut dmr = channel("DMR_CH");
+        dmr.mode = Mode::Dmr;
+        dmr.talkgroup = Some(99);
+        let mut fm = channel("FM_CH");
+        fm.mode = Mode::Fm;
+        fm.talkgroup = Some(99);
+        let out = UnknownTalkgroup.check(&[dmr, fm], &[]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].field, "talkgroup");
+    }
+
+    #[test]
+    fn apply_fixes_only_touches_fields_with_a_fix() {
+        let mut channels = vec![channel("NO_FIX_NEEDED")];
+        let diagnostics = vec![Diagnostic {
+            severity: Severity::Error,
+            row: 0,
+            field: "dmr_id",
+            message: "unused".into(),
+            fix: None,
+        }];
+        apply_fixes(&mut channels, &diagnostics);
+        assert_eq!(channels[0].name, "NO_FIX_NEEDED");
+    }
+}
@@ -0,0 +1,79 @@
+// Neutral in-memory codeplug model shared by all format backends.
+//
+// Every `Format` implementation reads into and writes out of these types, so a
+// field a given radio doesn't support simply falls back to that radio's own
+// default instead of being lost or causing a parse failure.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Fm,
+    Dmr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Power {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CallType {
+    Private,
+    #[default]
+    Group,
+    AllCall,
+}
+
+// A single DMR/analogue contact (talkgroup or private call).
+#[derive(Debug, Clone, Default)]
+pub struct Contact {
+    pub dmr_id: u32,
+    pub name: String,
+    pub call_type: CallType,
+}
+
+// A single channel. Fields a format doesn't know about are left at their
+// `Default`, which each writer maps to that radio's own "no value" constant.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub name: String,
+    pub mode: Mode,
+    pub bandwidth: f64, // kHz, e.g. 12.5
+    pub tx_freq: f64,   // MHz
+    pub rx_freq: f64,   // MHz
+    pub power: Power,
+    pub ctcss_rx: Option<f64>, // decode tone, Hz
+    pub ctcss_tx: Option<f64>, // encode tone, Hz
+    pub colour: i32,           // DMR colour code
+    pub slot: i32,             // DMR timeslot, 1 or 2
+    pub talkgroup: Option<u32>,
+    pub call_type: CallType,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Channel {
+            name: String::new(),
+            mode: Mode::Fm,
+            bandwidth: 12.5,
+            tx_freq: 0.0,
+            rx_freq: 0.0,
+            power: Power::default(),
+            ctcss_rx: None,
+            ctcss_tx: None,
+            colour: 1,
+            slot: 1,
+            talkgroup: None,
+            call_type: CallType::default(),
+        }
+    }
+}
+
+// A named group of channels, as shown in the radio's zone list.
+#[derive(Debug, Clone, Default)]
+pub struct Zone {
+    pub name: String,
+    pub channels: Vec<String>,
+}
@@ -0,0 +1,339 @@
+// Pluggable channel-to-zone partitioning strategies.
+//
+// `write_channels` used to hardcode a merge-if-under-50/split-if-over-100
+// heuristic keyed on `HashMap` iteration order, so `zone.csv` output order
+// was non-deterministic and the thresholds couldn't be tuned to a radio's
+// real zone/channel-per-zone limits. `ZoneStrategy` replaces that with an
+// explicit, `--zone-strategy`-selected partitioning; every strategy here
+// sorts its output so the emitted zones are byte-stable across runs.
+
+use crate::model::{Channel, Zone};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+
+pub trait ZoneStrategy {
+    fn partition(&self, channels: &[Channel]) -> Result<Vec<Zone>, Box<dyn Error>>;
+}
+
+// Below this combined size, the two smallest zones are merged together
+// (as long as the merged zone still fits the maximum zone size).
+const MIN_ZONE_SIZE: usize = 50;
+
+// Group channels by talkgroup (the original behavior), then rebalance so no
+// zone has more than `max_zone_size` channels.
+pub struct TalkgroupStrategy {
+    pub max_zone_size: usize,
+}
+
+impl ZoneStrategy for TalkgroupStrategy {
+    fn partition(&self, channels: &[Channel]) -> Result<Vec<Zone>, Box<dyn Error>> {
+        let mut buckets: HashMap<String, Vec<String>> = HashMap::new();
+        for ch in channels {
+            let key = match ch.talkgroup {
+                Some(tg) => tg.to_string(),
+                None => String::from("Default"),
+            };
+            buckets.entry(key).or_default().push(ch.name.clone());
+        }
+        Ok(rebalance(buckets, MIN_ZONE_SIZE, self.max_zone_size))
+    }
+}
+
+// Group channels by 2m vs 70cm (everything else falls into "other"), then
+// rebalance so no zone has more than `max_zone_size` channels.
+pub struct BandStrategy {
+    pub max_zone_size: usize,
+}
+
+fn band_name(ch: &Channel) -> &'static str {
+    if ch.rx_freq >= 144.0 && ch.rx_freq <= 148.0 {
+        "2m"
+    } else if ch.rx_freq >= 420.0 && ch.rx_freq <= 450.0 {
+        "70cm"
+    } else {
+        "other"
+    }
+}
+
+impl ZoneStrategy for BandStrategy {
+    fn partition(&self, channels: &[Channel]) -> Result<Vec<Zone>, Box<dyn Error>> {
+        let mut buckets: HashMap<String, Vec<String>> = HashMap::new();
+        for ch in channels {
+            buckets
+                .entry(band_name(ch).to_owned())
+                .or_default()
+                .push(ch.name.clone());
+        }
+        // A min size of 0 means bands are never merged into each other.
+        Ok(rebalance(buckets, 0, self.max_zone_size))
+    }
+}
+
+// Sort channels by (rx_freq, name) for a stable key, then greedily assign
+// each to the currently-smallest zone so the max zone size is minimized.
+pub struct BalancedKWayStrategy {
+    pub max_zone_size: Option<usize>,
+    pub max_zones: Option<usize>,
+}
+
+impl ZoneStrategy for BalancedKWayStrategy {
+    fn partition(&self, channels: &[Channel]) -> Result<Vec<Zone>, Box<dyn Error>> {
+        if channels.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut sorted: Vec<&Channel> = channels.iter().collect();
+        sorted.sort_by(|a, b| {
+            a.rx_freq
+                .partial_cmp(&b.rx_freq)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let total = sorted.len();
+        let zone_count = match (self.max_zones, self.max_zone_size) {
+            (Some(n), _) => n,
+            (None, Some(size)) if size > 0 => total.div_ceil(size),
+            _ => 1,
+        };
+        if zone_count == 0 {
+            return Err("balanced-kway: --max-zones must be at least 1".into());
+        }
+        if let Some(size) = self.max_zone_size {
+            if zone_count.saturating_mul(size) < total {
+                return Err(format!(
+                    "balanced-kway: {} channels cannot fit in {} zone(s) of at most {} channels each",
+                    total, zone_count, size
+                )
+                .into());
+            }
+        }
+
+        let mut zones: Vec<Vec<String>> = vec![Vec::new(); zone_count];
+        // Min-heap of (zone size, zone index); ties broken by index so
+        // results are reproducible across runs.
+        let mut heap: BinaryHeap<Reverse<(usize, usize)>> =
+            (0..zone_count).map(|i| Reverse((0, i))).collect();
+        for ch in sorted {
+            let Reverse((size, idx)) = heap.pop().unwrap();
+            zones[idx].push(ch.name.clone());
+            heap.push(Reverse((size + 1, idx)));
+        }
+
+        Ok(zones
+            .into_iter()
+            .enumerate()
+            .map(|(i, channels)| Zone {
+                name: format!("Zone{}", i + 1),
+                channels,
+            })
+            .collect())
+    }
+}
+
+// Repeatedly merge the two smallest zones together while under `min_size`,
+// and split any zone over `max_size` in half, until both hold everywhere.
+fn rebalance(mut zones: HashMap<String, Vec<String>>, min_size: usize, max_size: usize) -> Vec<Zone> {
+    if zones.is_empty() {
+        return Vec::new();
+    }
+    loop {
+        let mut clusters: Vec<(usize, String)> =
+            zones.iter().map(|kv| (kv.1.len(), kv.0.clone())).collect();
+        clusters.sort();
+        let merged_size = clusters[0].0 + clusters.get(1).map_or(0, |c| c.0);
+        if clusters.len() > 2 && merged_size < min_size && merged_size <= max_size {
+            let mut head = zones.remove(&clusters[0].1).unwrap();
+            let mut tail = zones.remove(&clusters[1].1).unwrap();
+            head.append(&mut tail);
+            let key = format!("{}/{}", clusters[0].1, clusters[1].1);
+            zones.insert(key, head);
+            continue;
+        }
+        let last = clusters.len() - 1;
+        if clusters[last].0 > max_size {
+            let mut to_split = zones.remove(&clusters[last].1).unwrap();
+            to_split.sort();
+            let second = to_split.split_off(clusters[last].0 / 2);
+            let mut sub_idx = 1;
+            loop {
+                let k1 = format!("{}_{}", clusters[last].1, sub_idx);
+                sub_idx += 1;
+                if let std::collections::hash_map::Entry::Vacant(e) = zones.entry(k1) {
+                    e.insert(to_split);
+                    break;
+                }
+            }
+            loop {
+                let k2 = format!("{}_{}", clusters[last].1, sub_idx);
+                sub_idx += 1;
+                if let std::collections::hash_map::Entry::Vacant(e) = zones.entry(k2) {
+                    e.insert(second);
+                    break;
+                }
+            }
+            continue;
+        }
+        break;
+    }
+    let mut keys: Vec<String> = zones.keys().cloned().collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|k| {
+            let mut chans = zones.remove(&k).unwrap();
+            chans.sort();
+            Zone {
+                name: k,
+                channels: chans,
+            }
+        })
+        .collect()
+}
+
+// Resolve a `--zone-strategy` flag value to its strategy implementation.
+pub fn by_name(
+    name: &str,
+    max_zone_size: usize,
+    max_zones: Option<usize>,
+) -> Result<Box<dyn ZoneStrategy>, Box<dyn Error>> {
+    match name {
+        "talkgroup" => Ok(Box::new(TalkgroupStrategy { max_zone_size })),
+        "band" => Ok(Box::new(BandStrategy { max_zone_size })),
+        "balanced-kway" => Ok(Box::new(BalancedKWayStrategy {
+            max_zone_size: Some(max_zone_size),
+            max_zones,
+        })),
+        other => Err(format!(
+            "unknown zone strategy '{}' (expected talkgroup, band, balanced-kway)",
+            other
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(name: &str, talkgroup: Option<u32>, rx_freq: f64) -> Channel {
+        Channel {
+            name: name.to_owned(),
+            talkgroup,
+            rx_freq,
+            tx_freq: rx_freq,
+            ..Channel::default()
+        }
+    }
+
+    #[test]
+    fn talkgroup_strategy_groups_by_talkgroup_and_default() {
+        let channels = vec![
+            channel("A", Some(1), 145.0),
+            channel("B", Some(1), 145.1),
+            channel("C", None, 145.2),
+        ];
+        let strategy = TalkgroupStrategy { max_zone_size: 100 };
+        let zones = strategy.partition(&channels).unwrap();
+        // Both groups are under MIN_ZONE_SIZE, but `rebalance` only merges
+        // while more than two zones remain, so the two stay separate.
+        assert_eq!(zones.len(), 2);
+        let total: usize = zones.iter().map(|z| z.channels.len()).sum();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn band_strategy_separates_2m_70cm_and_other() {
+        let channels = vec![
+            channel("A", None, 146.0),
+            channel("B", None, 440.0),
+            channel("C", None, 27.205),
+        ];
+        let strategy = BandStrategy { max_zone_size: 100 };
+        let zones = strategy.partition(&channels).unwrap();
+        // Bands are never merged into each other (min_size 0), so each band
+        // stays a separate zone even though each is tiny.
+        assert_eq!(zones.len(), 3);
+        let names: Vec<&str> = {
+            let mut n: Vec<&str> = zones.iter().flat_map(|z| z.name.split('/')).collect();
+            n.sort();
+            n
+        };
+        assert_eq!(names, vec!["2m", "70cm", "other"]);
+    }
+
+    #[test]
+    fn balanced_kway_spreads_channels_evenly() {
+        let channels: Vec<Channel> = (0..6)
+            .map(|i| channel(&format!("CH{}", i), None, 145.0 + i as f64 * 0.01))
+            .collect();
+        let strategy = BalancedKWayStrategy {
+            max_zone_size: None,
+            max_zones: Some(3),
+        };
+        let zones = strategy.partition(&channels).unwrap();
+        assert_eq!(zones.len(), 3);
+        assert!(zones.iter().all(|z| z.channels.len() == 2));
+    }
+
+    #[test]
+    fn balanced_kway_errors_on_infeasible_constraints() {
+        let channels: Vec<Channel> = (0..3)
+            .map(|i| channel(&format!("CH{}", i), None, 145.0 + i as f64 * 0.01))
+            .collect();
+        let strategy = BalancedKWayStrategy {
+            max_zone_size: Some(1),
+            max_zones: Some(2),
+        };
+        assert!(strategy.partition(&channels).is_err());
+    }
+
+    #[test]
+    fn balanced_kway_on_empty_input_returns_no_zones() {
+        let strategy = BalancedKWayStrategy {
+            max_zone_size: Some(10),
+            max_zones: None,
+        };
+        assert_eq!(strategy.partition(&[]).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn talkgroup_strategy_on_empty_input_does_not_panic() {
+        let strategy = TalkgroupStrategy { max_zone_size: 100 };
+        assert_eq!(strategy.partition(&[]).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn rebalance_merges_zones_under_min_size() {
+        let mut zones = HashMap::new();
+        zones.insert("a".to_string(), vec!["c1".to_string()]);
+        zones.insert("b".to_string(), vec!["c2".to_string()]);
+        zones.insert("c".to_string(), vec!["c3".to_string()]);
+        let out = rebalance(zones, 50, 100);
+        // The two smallest zones get merged together, but `rebalance` never
+        // merges down to a single zone.
+        assert_eq!(out.len(), 2);
+        let total: usize = out.iter().map(|z| z.channels.len()).sum();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn rebalance_splits_zones_over_max_size() {
+        let mut zones = HashMap::new();
+        let channels: Vec<String> = (0..10).map(|i| format!("CH{}", i)).collect();
+        zones.insert("big".to_string(), channels);
+        let out = rebalance(zones, 0, 4);
+        assert!(out.iter().all(|z| z.channels.len() <= 4));
+        let total: usize = out.iter().map(|z| z.channels.len()).sum();
+        assert_eq!(total, 10);
+    }
+
+    #[test]
+    fn rebalance_on_empty_input_does_not_panic() {
+        assert_eq!(rebalance(HashMap::new(), 50, 100).len(), 0);
+    }
+
+    #[test]
+    fn by_name_rejects_unknown_strategy() {
+        assert!(by_name("nonsense", 100, None).is_err());
+    }
+}